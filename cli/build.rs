@@ -0,0 +1,172 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{env, fs};
+
+// `cli/src/interface.rs` does `include!(concat!(env!("OUT_DIR"),
+// "/guest_interface.rs"))`, and `OUT_DIR` is private to the crate whose
+// build script populates it — `methods/build.rs` generating this same file
+// into its own `OUT_DIR` doesn't make it visible here. Regenerate it
+// straight into this crate's `OUT_DIR` instead, from the same ABI Foundry
+// emits.
+//
+// Name of the application contract whose ABI is used to generate the
+// `GuestInterface` skeleton. Foundry writes its build artifacts to
+// `out/<name>.sol/<name>.json` relative to the workspace root.
+const GUEST_INTERFACE_CONTRACT: &str = "EvenNumber";
+const GUEST_INTERFACE_ABI_PATH: &str = "../out";
+const GUEST_INTERFACE_OUT_FILE: &str = "guest_interface.rs";
+
+fn main() {
+    generate_guest_interface(GUEST_INTERFACE_CONTRACT);
+}
+
+/// Reads the ABI JSON Foundry emits for `contract_name` and writes a
+/// generated `GuestInterface` skeleton to `OUT_DIR/guest_interface.rs`: one
+/// `encode_*_calldata` function per non-view callable, with ABI types mapped
+/// to their `alloy` equivalents and the trailing `(post_state_digest, seal)`
+/// pair appended automatically.
+///
+/// Kept in sync with `methods/build.rs`'s copy of this function — see the
+/// module comment above for why this crate needs its own.
+fn generate_guest_interface(contract_name: &str) {
+    let abi_path = format!("{GUEST_INTERFACE_ABI_PATH}/{contract_name}.sol/{contract_name}.json");
+    println!("cargo:rerun-if-changed={abi_path}");
+
+    let Ok(abi_contents) = fs::read_to_string(&abi_path) else {
+        // Foundry hasn't built the contract yet (e.g. a fresh checkout); skip
+        // codegen rather than fail the whole build.
+        return;
+    };
+    let artifact: serde_json::Value = serde_json::from_str(&abi_contents)
+        .unwrap_or_else(|err| panic!("failed to parse ABI at {abi_path}: {err}"));
+
+    let functions = artifact["abi"].as_array().cloned().unwrap_or_default();
+    let generated: String = functions
+        .iter()
+        .filter(|entry| entry["type"] == "function" && entry["stateMutability"] != "view")
+        .map(render_encode_fn)
+        .collect();
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_path = std::path::Path::new(&out_dir).join(GUEST_INTERFACE_OUT_FILE);
+    fs::write(&out_path, generated).unwrap_or_else(|err| {
+        panic!("failed to write {}: {}", out_path.display(), err);
+    });
+}
+
+/// Maps a Solidity ABI type name to the `alloy` Rust type used to represent
+/// it in generated `encode_*_calldata` signatures.
+fn sol_type_to_rust(sol_type: &str) -> &str {
+    match sol_type {
+        "uint256" => "alloy_primitives::U256",
+        "address" => "alloy_primitives::Address",
+        "bytes32" => "alloy_primitives::FixedBytes<32>",
+        "bytes" => "Vec<u8>",
+        "bool" => "bool",
+        other => panic!("guest interface codegen: unsupported ABI type `{other}`"),
+    }
+}
+
+/// Renders one `encode_<name>_calldata` function for a single ABI function
+/// entry. The function's final two ABI parameters are always the proof's
+/// `post_state_digest` and `seal`; the remaining leading parameters are the
+/// application's journal fields.
+fn render_encode_fn(function: &serde_json::Value) -> String {
+    let name = function["name"].as_str().unwrap_or_default();
+    let inputs = function["inputs"].as_array().cloned().unwrap_or_default();
+    let params: Vec<(String, String)> = inputs
+        .iter()
+        .map(|input| {
+            (
+                input["name"].as_str().unwrap_or_default().to_string(),
+                input["type"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    let journal_params = &params[..params.len().saturating_sub(2)];
+
+    let signature = format!(
+        "{name}({})",
+        params.iter().map(|(_, ty)| ty.as_str()).collect::<Vec<_>>().join(",")
+    );
+    let sol_call_params = params
+        .iter()
+        .map(|(arg, ty)| format!("{ty} {arg}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let fn_args = journal_params
+        .iter()
+        .map(|(arg, ty)| format!("{arg}: {}", sol_type_to_rust(ty)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let call_fields = journal_params
+        .iter()
+        .map(|(arg, _)| format!("{arg},"))
+        .collect::<Vec<_>>()
+        .join("\n        ");
+    // `sol!` names the generated call struct after the function's original
+    // case plus `Call` (e.g. `set` -> `setCall`), not a capitalized variant.
+    let call_name = format!("{name}Call");
+
+    format!(
+        r#"
+/// Generated from the ABI entry for `{signature}`.
+pub fn encode_{name}_calldata({fn_args}, post_state_digest: alloy_primitives::FixedBytes<32>, seal: Vec<u8>) -> anyhow::Result<Vec<u8>> {{
+    alloy_sol_types::sol! {{
+        function {signature_decl};
+    }}
+    use alloy_sol_types::SolCall;
+    Ok({call_name} {{
+        {call_fields}
+        post_state_digest,
+        seal,
+    }}
+    .abi_encode())
+}}
+"#,
+        signature_decl = format!("{name}({sol_call_params})"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sol_type_to_rust_maps_every_type_the_example_contract_uses() {
+        assert_eq!(sol_type_to_rust("uint256"), "alloy_primitives::U256");
+        assert_eq!(sol_type_to_rust("address"), "alloy_primitives::Address");
+        assert_eq!(sol_type_to_rust("bytes32"), "alloy_primitives::FixedBytes<32>");
+        assert_eq!(sol_type_to_rust("bytes"), "Vec<u8>");
+        assert_eq!(sol_type_to_rust("bool"), "bool");
+    }
+
+    #[test]
+    fn render_encode_fn_names_the_call_struct_after_sol_s_actual_casing() {
+        let function = serde_json::json!({
+            "type": "function",
+            "name": "set",
+            "inputs": [
+                {"name": "x", "type": "uint256"},
+                {"name": "post_state_digest", "type": "bytes32"},
+                {"name": "seal", "type": "bytes"},
+            ],
+        });
+        let rendered = render_encode_fn(&function);
+        assert!(rendered.contains("pub fn encode_set_calldata(x: alloy_primitives::U256, post_state_digest"));
+        assert!(rendered.contains("Ok(setCall {"));
+        assert!(!rendered.contains("SetCall"));
+    }
+}
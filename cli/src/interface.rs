@@ -15,7 +15,7 @@
 use std::str::FromStr;
 
 use alloy_primitives::{FixedBytes, U256};
-use alloy_sol_types::{sol, SolInterface, SolValue};
+use alloy_sol_types::SolValue;
 use anyhow::Result;
 use risc0_ethereum_sdk::cli::GuestInterface;
 
@@ -23,14 +23,11 @@ use risc0_ethereum_sdk::cli::GuestInterface;
 // that lets you define how to parse and serialize the guest input and calldata
 // so that your contract can interact with the RISC Zero zkVM and Bonsai.
 
-// `IEvenNumber`` interface automatically generated via the alloy `sol!` macro.
-// The `set` function is then used as part of the `calldata` function of the
-// `EvenNumberInterface`.
-sol! {
-    interface IEvenNumber {
-        function set(uint256 x, bytes32 post_state_digest, bytes calldata seal);
-    }
-}
+// `encode_set_calldata` is generated by `build.rs` from the `EvenNumber`
+// contract's ABI (out/EvenNumber.sol/EvenNumber.json): one `encode_*_calldata`
+// function per non-view ABI entry, with ABI types mapped to `alloy` types and
+// the trailing `(post_state_digest, seal)` pair appended automatically.
+include!(concat!(env!("OUT_DIR"), "/guest_interface.rs"));
 
 /// Implementation of `GuestInterface` for the `EvenNumber` example application.
 pub struct EvenNumberInterface;
@@ -55,12 +52,14 @@ impl GuestInterface for EvenNumberInterface {
         // Decode the journal. Must match what was written in the guest with `env::commit_slice`
         let x = U256::abi_decode(&journal, true)?;
 
-        // Encode the function call for `IEvenNumber.set(x)`
-        Ok(IEvenNumber::IEvenNumberCalls::set(IEvenNumber::setCall {
-            x,
-            post_state_digest,
-            seal,
-        })
-        .abi_encode())
+        // Encode the function call for `IEvenNumber.set(x)` using the
+        // generated encoder instead of a hand-written `SolInterface` call.
+        encode_set_calldata(x, post_state_digest, seal)
+    }
+
+    /// Canonical signature of `IEvenNumber.set`, used by the `Callback`
+    /// command to derive the function selector.
+    fn callback_signature(&self) -> Option<&str> {
+        Some("set(uint256,bytes32,bytes)")
     }
 }
@@ -50,6 +50,13 @@ library Elf {
 const SOLIDITY_IMAGE_ID_PATH: &str = "../contracts/ImageID.sol";
 const SOLIDITY_ELF_PATH: &str = "../tests/Elf.sol";
 
+// Name of the application contract whose ABI is used to generate the
+// `GuestInterface` skeleton. Foundry writes its build artifacts to
+// `out/<name>.sol/<name>.json` relative to the workspace root.
+const GUEST_INTERFACE_CONTRACT: &str = "EvenNumber";
+const GUEST_INTERFACE_ABI_PATH: &str = "../out";
+const GUEST_INTERFACE_OUT_FILE: &str = "guest_interface.rs";
+
 fn main() {
     let use_docker = env::var("RISC0_USE_DOCKER").ok().map(|_| DockerOptions {
         root_dir: Some("../".into()),
@@ -105,4 +112,113 @@ fn main() {
         .unwrap_or_else(|e| {
             panic!("failed to format {SOLIDITY_IMAGE_ID_PATH}, {SOLIDITY_ELF_PATH}: {e}")
         });
+
+    generate_guest_interface(GUEST_INTERFACE_CONTRACT);
+}
+
+/// Reads the ABI JSON Foundry emits for `contract_name` and writes a
+/// generated `GuestInterface` skeleton to `OUT_DIR/guest_interface.rs`: one
+/// `encode_*_calldata` function per non-view callable, with ABI types mapped
+/// to their `alloy` equivalents and the trailing `(post_state_digest, seal)`
+/// pair appended automatically. The file lands in this crate's own
+/// `OUT_DIR`; `cli/build.rs` generates the same file into the CLI crate's
+/// `OUT_DIR`, which is the copy `cli/src/interface.rs` `include!`s.
+fn generate_guest_interface(contract_name: &str) {
+    let abi_path = format!("{GUEST_INTERFACE_ABI_PATH}/{contract_name}.sol/{contract_name}.json");
+    println!("cargo:rerun-if-changed={abi_path}");
+
+    let Ok(abi_contents) = fs::read_to_string(&abi_path) else {
+        // Foundry hasn't built the contract yet (e.g. a fresh checkout); skip
+        // codegen rather than fail the whole build.
+        return;
+    };
+    let artifact: serde_json::Value = serde_json::from_str(&abi_contents)
+        .unwrap_or_else(|err| panic!("failed to parse ABI at {abi_path}: {err}"));
+
+    let functions = artifact["abi"].as_array().cloned().unwrap_or_default();
+    let generated: String = functions
+        .iter()
+        .filter(|entry| entry["type"] == "function" && entry["stateMutability"] != "view")
+        .map(render_encode_fn)
+        .collect();
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_path = std::path::Path::new(&out_dir).join(GUEST_INTERFACE_OUT_FILE);
+    fs::write(&out_path, generated).unwrap_or_else(|err| {
+        panic!("failed to write {}: {}", out_path.display(), err);
+    });
+}
+
+/// Maps a Solidity ABI type name to the `alloy` Rust type used to represent
+/// it in generated `encode_*_calldata` signatures.
+fn sol_type_to_rust(sol_type: &str) -> &str {
+    match sol_type {
+        "uint256" => "alloy_primitives::U256",
+        "address" => "alloy_primitives::Address",
+        "bytes32" => "alloy_primitives::FixedBytes<32>",
+        "bytes" => "Vec<u8>",
+        "bool" => "bool",
+        other => panic!("guest interface codegen: unsupported ABI type `{other}`"),
+    }
+}
+
+/// Renders one `encode_<name>_calldata` function for a single ABI function
+/// entry. The function's final two ABI parameters are always the proof's
+/// `post_state_digest` and `seal`; the remaining leading parameters are the
+/// application's journal fields.
+fn render_encode_fn(function: &serde_json::Value) -> String {
+    let name = function["name"].as_str().unwrap_or_default();
+    let inputs = function["inputs"].as_array().cloned().unwrap_or_default();
+    let params: Vec<(String, String)> = inputs
+        .iter()
+        .map(|input| {
+            (
+                input["name"].as_str().unwrap_or_default().to_string(),
+                input["type"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    let journal_params = &params[..params.len().saturating_sub(2)];
+
+    let signature = format!(
+        "{name}({})",
+        params.iter().map(|(_, ty)| ty.as_str()).collect::<Vec<_>>().join(",")
+    );
+    let sol_call_params = params
+        .iter()
+        .map(|(arg, ty)| format!("{ty} {arg}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let fn_args = journal_params
+        .iter()
+        .map(|(arg, ty)| format!("{arg}: {}", sol_type_to_rust(ty)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let call_fields = journal_params
+        .iter()
+        .map(|(arg, _)| format!("{arg},"))
+        .collect::<Vec<_>>()
+        .join("\n        ");
+    // `sol!` names the generated call struct after the function's original
+    // case plus `Call` (e.g. `set` -> `setCall`), not a capitalized variant.
+    let call_name = format!("{name}Call");
+
+    format!(
+        r#"
+/// Generated from the ABI entry for `{signature}`.
+pub fn encode_{name}_calldata({fn_args}, post_state_digest: alloy_primitives::FixedBytes<32>, seal: Vec<u8>) -> anyhow::Result<Vec<u8>> {{
+    alloy_sol_types::sol! {{
+        function {signature_decl};
+    }}
+    use alloy_sol_types::SolCall;
+    Ok({call_name} {{
+        {call_fields}
+        post_state_digest,
+        seal,
+    }}
+    .abi_encode())
+}}
+"#,
+        signature_decl = format!("{name}({sol_call_params})"),
+    )
 }
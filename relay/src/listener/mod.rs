@@ -0,0 +1,213 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watches the proxy contract for `CallbackRequestFilter` events and feeds
+//! them into `Storage` as new Bonsai proof requests.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_stream::try_stream;
+use bonsai_proxy_contract::CallbackRequestFilter;
+use ethers::{
+    contract::EthEvent,
+    prelude::Middleware,
+    types::{Address, Filter, FilterKind, U64},
+};
+use futures::Stream;
+use tokio::sync::oneshot;
+
+use crate::storage::{ProofRequstInformation, Storage};
+
+/// How often to poll `eth_getFilterChanges` for the live event stream.
+const FILTER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Once the catch-up cursor is within this many blocks of the chain head,
+/// catch-up hands off to the live event stream. Blocks inside this gap are
+/// treated as non-final, so a short reorg near the tip gets re-processed by
+/// the live stream rather than silently skipped.
+pub const CATCH_UP_END_GAP: u64 = 10;
+
+/// Maximum number of blocks fetched in a single `eth_getLogs` call while
+/// catching up, to keep each request bounded regardless of how far behind
+/// the relay has fallen.
+const CATCH_UP_CHUNK_SIZE: u64 = 10_000;
+
+/// Fetches every `CallbackRequestFilter` log emitted since `storage`'s last
+/// checkpoint, in bounded block ranges, persisting the checkpoint after each
+/// range so a crash mid catch-up resumes rather than re-scans from genesis.
+/// Returns once the cursor is within [`CATCH_UP_END_GAP`] blocks of the
+/// current head, signaling completion on `done` so the caller can hand off
+/// to a live subscription knowing the historical backlog is drained.
+pub async fn catch_up<M, S>(
+    client: Arc<M>,
+    proxy_address: Address,
+    storage: &S,
+    done: oneshot::Sender<()>,
+) -> Result<()>
+where
+    M: Middleware + 'static,
+    S: Storage,
+{
+    loop {
+        let head: u64 = client
+            .get_block_number()
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to fetch chain head: {err}"))?
+            .as_u64();
+        let from_block = storage.get_last_processed_block().await?.unwrap_or(0);
+
+        if head.saturating_sub(from_block) <= CATCH_UP_END_GAP {
+            break;
+        }
+
+        let to_block = (from_block + CATCH_UP_CHUNK_SIZE).min(head - CATCH_UP_END_GAP);
+        let filter = Filter::new()
+            .address(proxy_address)
+            .event(&CallbackRequestFilter::abi_signature())
+            .from_block(U64::from(from_block))
+            .to_block(U64::from(to_block));
+
+        let logs = client
+            .get_logs(&filter)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to fetch logs: {err}"))?;
+
+        for log in logs {
+            // No prover request ID exists for this event yet: catch-up only
+            // records the event and submits nothing to a prover. Key this
+            // record on the event's on-chain identity instead — (transaction hash, log index) is unique per
+            // log — so that catching up over a range with more than one
+            // missed callback doesn't collide every event after the first
+            // onto the same storage key.
+            let key = catch_up_key(log.transaction_hash.unwrap_or_default(), log.log_index.unwrap_or_default());
+            let event: CallbackRequestFilter = ethers::contract::parse_log(log)?;
+            storage
+                .add_new_bonsai_proof_request(ProofRequstInformation {
+                    proof_request_id: key,
+                    callback_proof_request_event: event,
+                })
+                .await
+                .or_else(|err| match err {
+                    crate::storage::Error::ProofAlreadyExists { .. } => Ok(()),
+                    err => Err(err),
+                })?;
+        }
+
+        storage.set_last_processed_block(to_block).await?;
+    }
+
+    // The receiver may already be gone if the caller stopped waiting; that's
+    // not a catch-up failure.
+    let _ = done.send(());
+    Ok(())
+}
+
+/// Synthesizes a storage key for a catch-up-discovered request before its
+/// real Bonsai proof request ID is known: `(transaction hash, log index)` is
+/// unique per log, so distinct events never collide on the same key the way
+/// a fixed placeholder would.
+fn catch_up_key(tx_hash: ethers::types::H256, log_index: ethers::types::U256) -> String {
+    format!("catchup:{tx_hash:?}:{log_index}")
+}
+
+/// Streams decoded `CallbackRequestFilter` events from the proxy contract by
+/// polling `eth_getFilterChanges`, rather than repeatedly re-scanning with
+/// `eth_getLogs`. If the node drops the installed filter (the "filter not
+/// found" error every client eventually returns, e.g. after a restart or
+/// idle timeout), a fresh filter is installed and polling continues — the
+/// caller sees a continuous stream, not an error, as long as the node is
+/// reachable.
+pub fn callback_request_stream<M>(
+    client: Arc<M>,
+    proxy_address: Address,
+) -> impl Stream<Item = Result<CallbackRequestFilter>>
+where
+    M: Middleware + 'static,
+{
+    try_stream! {
+        let filter = Filter::new()
+            .address(proxy_address)
+            .event(&CallbackRequestFilter::abi_signature());
+        let mut filter_id = client
+            .new_filter(FilterKind::Logs(&filter))
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to install log filter: {err}"))?;
+
+        loop {
+            tokio::time::sleep(FILTER_POLL_INTERVAL).await;
+
+            let changes = match client.get_filter_changes(filter_id).await {
+                Ok(changes) => changes,
+                Err(err) if is_filter_not_found(&err) => {
+                    // The node dropped our filter (restart, idle timeout);
+                    // reinstall it and resume polling rather than bubbling
+                    // up an error the caller would have to retry anyway.
+                    filter_id = client
+                        .new_filter(FilterKind::Logs(&filter))
+                        .await
+                        .map_err(|err| anyhow::anyhow!("failed to reinstall log filter: {err}"))?;
+                    continue;
+                }
+                Err(err) => Err(anyhow::anyhow!("failed to poll log filter: {err}"))?,
+            };
+
+            for log in changes {
+                yield ethers::contract::parse_log(log)?;
+            }
+        }
+    }
+}
+
+/// Whether `err` is the "filter not found" error every client eventually
+/// returns once a filter is dropped node-side, as opposed to some other
+/// failure that should bubble up instead of triggering a silent reinstall.
+fn is_filter_not_found<E: std::fmt::Display>(err: &E) -> bool {
+    err.to_string().contains("filter not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+
+    #[test]
+    fn is_filter_not_found_matches_only_that_error() {
+        assert!(is_filter_not_found(&"filter not found"));
+        assert!(is_filter_not_found(&"Error: filter not found (code -32000)"));
+        assert!(!is_filter_not_found(&"connection refused"));
+    }
+
+    #[test]
+    fn catch_up_key_is_unique_per_log_and_stable_across_retries() {
+        let tx_a = H256::random();
+        let tx_b = H256::random();
+
+        assert_ne!(
+            catch_up_key(tx_a, U256::from(0)),
+            catch_up_key(tx_a, U256::from(1)),
+            "two logs in the same transaction must not collide"
+        );
+        assert_ne!(
+            catch_up_key(tx_a, U256::from(0)),
+            catch_up_key(tx_b, U256::from(0)),
+            "logs with the same index in different transactions must not collide"
+        );
+        assert_eq!(
+            catch_up_key(tx_a, U256::from(0)),
+            catch_up_key(tx_a, U256::from(0)),
+            "re-processing the same log (e.g. after a crash mid-range) must be idempotent"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`ProverBackend`] backed by the remote Bonsai proving service.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bonsai_sdk::client::Client;
+
+use crate::storage::ProofID;
+
+use super::{ProofOutput, ProverBackend};
+
+/// How long to wait between polls of a Bonsai proof request's status.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct BonsaiProverBackend {
+    client: Client,
+}
+
+impl BonsaiProverBackend {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ProverBackend for BonsaiProverBackend {
+    async fn submit(&self, image_id: [u8; 32], input: Vec<u8>) -> Result<ProofID> {
+        let client = self.client.clone();
+        tokio::task::spawn_blocking(move || {
+            let image_id_hex = hex::encode(image_id);
+            let input_id = client.upload_input(input)?;
+            let session = client.create_session(image_id_hex, input_id, vec![])?;
+            anyhow::Ok(session.uuid)
+        })
+        .await?
+    }
+
+    async fn wait_for_completion(&self, proof_id: &ProofID) -> Result<()> {
+        loop {
+            let status = {
+                let client = self.client.clone();
+                let proof_id = proof_id.clone();
+                tokio::task::spawn_blocking(move || client.get_status(&proof_id)).await??
+            };
+            match status.status.as_str() {
+                "SUCCEEDED" => return Ok(()),
+                "RUNNING" => tokio::time::sleep(POLL_INTERVAL).await,
+                other => anyhow::bail!("bonsai proof request {proof_id} ended in status {other}"),
+            }
+        }
+    }
+
+    async fn fetch_output(&self, proof_id: &ProofID) -> Result<ProofOutput> {
+        let client = self.client.clone();
+        let proof_id = proof_id.clone();
+        let receipt =
+            tokio::task::spawn_blocking(move || client.get_receipt(&proof_id)).await??;
+        Ok(ProofOutput {
+            journal: receipt.journal,
+            seal: receipt.seal,
+        })
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Abstracts the relay's two managers over the proving system that actually
+//! runs the guest, so the same pipeline can drive a remote Bonsai service, a
+//! local in-process risc0 prover, or an SP1-style backend.
+
+pub mod bonsai;
+pub mod local;
+pub mod sp1;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::storage::ProofID;
+
+/// A completed proof, normalized to what the proxy contract's callback
+/// expects regardless of which backend produced it: the guest's committed
+/// journal, and a seal attesting to it.
+pub struct ProofOutput {
+    pub journal: Vec<u8>,
+    pub seal: Vec<u8>,
+}
+
+/// A proving backend. `BonsaiPendingProofManager` polls [`wait_for_completion`]
+/// until it resolves, and `BonsaiCompleteProofManager` then calls
+/// [`fetch_output`] to get the bytes to submit on-chain. [`submit`] creates
+/// a new proof request; neither manager calls it.
+///
+/// [`submit`]: ProverBackend::submit
+/// [`wait_for_completion`]: ProverBackend::wait_for_completion
+/// [`fetch_output`]: ProverBackend::fetch_output
+#[async_trait]
+pub trait ProverBackend: Clone + Send + Sync + 'static {
+    /// Submits a new proof request for `image_id` over `input`, returning an
+    /// ID this backend can later look up the request by.
+    async fn submit(&self, image_id: [u8; 32], input: Vec<u8>) -> Result<ProofID>;
+
+    /// Blocks until `proof_id` finishes proving (successfully or not),
+    /// without fetching its output.
+    async fn wait_for_completion(&self, proof_id: &ProofID) -> Result<()>;
+
+    /// Fetches the journal and seal for a request that has finished proving.
+    async fn fetch_output(&self, proof_id: &ProofID) -> Result<ProofOutput>;
+}
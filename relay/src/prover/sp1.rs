@@ -0,0 +1,86 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`ProverBackend`] that proves locally with SP1, for relay deployments
+//! that verify SP1 rather than risc0 proofs on-chain.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::storage::ProofID;
+
+use super::{ProofOutput, ProverBackend};
+
+/// Proves locally against a fixed SP1 ELF, generating a Groth16 proof sized
+/// for on-chain verification. As with [`LocalProverBackend`], proving runs
+/// to completion inside `submit`.
+///
+/// [`LocalProverBackend`]: super::local::LocalProverBackend
+#[derive(Clone)]
+pub struct Sp1ProverBackend {
+    elf: Arc<Vec<u8>>,
+    proofs: Arc<Mutex<HashMap<ProofID, SP1ProofWithPublicValues>>>,
+}
+
+impl Sp1ProverBackend {
+    pub fn new(elf: Vec<u8>) -> Self {
+        Self {
+            elf: Arc::new(elf),
+            proofs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl ProverBackend for Sp1ProverBackend {
+    async fn submit(&self, _image_id: [u8; 32], input: Vec<u8>) -> Result<ProofID> {
+        let elf = self.elf.clone();
+        let proof = tokio::task::spawn_blocking(move || {
+            let mut stdin = SP1Stdin::new();
+            stdin.write_slice(&input);
+            let client = ProverClient::new();
+            let (pk, _vk) = client.setup(&elf);
+            client.prove(&pk, stdin).groth16().run()
+        })
+        .await??;
+
+        let proof_id = Uuid::new_v4().to_string();
+        self.proofs.lock().await.insert(proof_id.clone(), proof);
+        Ok(proof_id)
+    }
+
+    async fn wait_for_completion(&self, proof_id: &ProofID) -> Result<()> {
+        if self.proofs.lock().await.contains_key(proof_id) {
+            Ok(())
+        } else {
+            anyhow::bail!("no local SP1 proof for proof {proof_id}")
+        }
+    }
+
+    async fn fetch_output(&self, proof_id: &ProofID) -> Result<ProofOutput> {
+        let proofs = self.proofs.lock().await;
+        let proof = proofs
+            .get(proof_id)
+            .ok_or_else(|| anyhow::anyhow!("no local SP1 proof for proof {proof_id}"))?;
+        Ok(ProofOutput {
+            journal: proof.public_values.to_vec(),
+            seal: proof.bytes(),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+
+use super::{Error, ProofID, ProofRequestState, ProofRequstInformation, Storage};
+
+/// Non-durable `Storage` implementation backed by a shared `HashMap`. Used
+/// in tests and for quick local runs; any request that is `Pending`,
+/// `Completed`, or `PreparingOnchain` when the process exits is lost, since
+/// nothing is written to disk.
+#[derive(Clone, Default)]
+pub struct InMemoryStorage {
+    requests: Arc<Mutex<HashMap<ProofID, (ProofRequstInformation, ProofRequestState)>>>,
+    last_processed_block: Arc<Mutex<Option<u64>>>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    async fn add_new_bonsai_proof_request(
+        &self,
+        info: ProofRequstInformation,
+    ) -> Result<(), Error> {
+        let mut requests = self.requests.lock().unwrap();
+        if requests.contains_key(&info.proof_request_id) {
+            return Err(Error::ProofAlreadyExists {
+                id: info.proof_request_id,
+            });
+        }
+        let id = info.proof_request_id.clone();
+        requests.insert(id, (info, ProofRequestState::Pending));
+        Ok(())
+    }
+
+    async fn transition_proof_request(
+        &self,
+        id: ProofID,
+        state: ProofRequestState,
+    ) -> Result<(), Error> {
+        let mut requests = self.requests.lock().unwrap();
+        if state == ProofRequestState::CompletedOnchain {
+            requests
+                .remove(&id)
+                .map(|_| ())
+                .ok_or(Error::ProofNotFound { id })
+        } else {
+            let entry = requests.get_mut(&id).ok_or_else(|| Error::ProofNotFound { id: id.clone() })?;
+            entry.1 = state;
+            Ok(())
+        }
+    }
+
+    async fn get_proof_request_state(&self, id: ProofID) -> Result<ProofRequestState, Error> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .get(&id)
+            .map(|(_, state)| *state)
+            .ok_or(Error::ProofNotFound { id })
+    }
+
+    async fn get_proof_request_information(
+        &self,
+        id: ProofID,
+    ) -> Result<ProofRequstInformation, Error> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .get(&id)
+            .map(|(info, _)| info.clone())
+            .ok_or(Error::ProofNotFound { id })
+    }
+
+    async fn fetch_requests_in_state(
+        &self,
+        state: ProofRequestState,
+    ) -> Result<Vec<ProofRequstInformation>, Error> {
+        let requests = self.requests.lock().unwrap();
+        Ok(requests
+            .values()
+            .filter(|(_, s)| *s == state)
+            .map(|(info, _)| info.clone())
+            .collect())
+    }
+
+    async fn remove_proof_request(&self, id: ProofID) -> Result<(), Error> {
+        let mut requests = self.requests.lock().unwrap();
+        requests
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(Error::ProofNotFound { id })
+    }
+
+    async fn get_last_processed_block(&self) -> Result<Option<u64>, Error> {
+        Ok(*self.last_processed_block.lock().unwrap())
+    }
+
+    async fn set_last_processed_block(&self, block: u64) -> Result<(), Error> {
+        *self.last_processed_block.lock().unwrap() = Some(block);
+        Ok(())
+    }
+}
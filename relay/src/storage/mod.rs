@@ -0,0 +1,109 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use bonsai_proxy_contract::CallbackRequestFilter;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub mod in_memory;
+pub mod persistent;
+
+/// Bonsai proof request ID, as returned by `bonsai_sdk::client::Client`.
+pub type ProofID = String;
+
+/// Everything needed to re-derive a `CallbackRequest`'s proof: the Bonsai
+/// proof request ID it was submitted under, and the on-chain event that
+/// triggered it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofRequstInformation {
+    pub proof_request_id: ProofID,
+    pub callback_proof_request_event: CallbackRequestFilter,
+}
+
+/// Where a proof request currently sits in the relay's pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofRequestState {
+    /// Submitted to Bonsai; awaiting completion.
+    Pending,
+    /// Bonsai has finished proving; awaiting batching for submission.
+    Completed,
+    /// Included in a batch that has been submitted on-chain; awaiting
+    /// confirmation.
+    PreparingOnchain,
+    /// Confirmed on-chain. Terminal: requests in this state are evicted
+    /// from storage rather than retained.
+    CompletedOnchain,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("proof request {id} not found")]
+    ProofNotFound { id: ProofID },
+    #[error("proof request {id} already exists")]
+    ProofAlreadyExists { id: ProofID },
+    #[error("storage backend error: {0}")]
+    Backend(#[from] anyhow::Error),
+}
+
+/// Durable record of in-flight `CallbackRequest`s and their state-machine
+/// position. Implementations back the `BonsaiPendingProofManager` and
+/// `BonsaiCompleteProofManager`. Each request's pipeline state lives here;
+/// the complete-proof manager's staged proofs and submitted-batch
+/// bookkeeping (tx hash, nonce, gas price) are held in memory only (see
+/// `persistent::recover` for how those requests are resumed).
+#[async_trait]
+pub trait Storage: Clone + Send + Sync + 'static {
+    /// Registers a newly submitted Bonsai proof request in the `Pending` state.
+    async fn add_new_bonsai_proof_request(
+        &self,
+        info: ProofRequstInformation,
+    ) -> Result<(), Error>;
+
+    /// Moves a proof request to `state`. Requests that reach
+    /// `ProofRequestState::CompletedOnchain` are evicted rather than stored.
+    async fn transition_proof_request(
+        &self,
+        id: ProofID,
+        state: ProofRequestState,
+    ) -> Result<(), Error>;
+
+    /// Returns the current pipeline state of a tracked proof request.
+    async fn get_proof_request_state(&self, id: ProofID) -> Result<ProofRequestState, Error>;
+
+    /// Returns the full record for a tracked proof request.
+    async fn get_proof_request_information(
+        &self,
+        id: ProofID,
+    ) -> Result<ProofRequstInformation, Error>;
+
+    /// Lists every tracked request currently in `state`, for resuming the
+    /// relevant manager on startup or polling for newly-ready work.
+    async fn fetch_requests_in_state(
+        &self,
+        state: ProofRequestState,
+    ) -> Result<Vec<ProofRequstInformation>, Error>;
+
+    /// Removes a request's record entirely (used once it reaches
+    /// `ProofRequestState::CompletedOnchain`).
+    async fn remove_proof_request(&self, id: ProofID) -> Result<(), Error>;
+
+    /// Returns the last Ethereum block number the event listener fully
+    /// processed, or `None` if it has never run. Used to resume catch-up
+    /// from where a prior run left off instead of re-scanning from genesis.
+    async fn get_last_processed_block(&self) -> Result<Option<u64>, Error>;
+
+    /// Records `block` as the last fully processed Ethereum block.
+    async fn set_last_processed_block(&self, block: u64) -> Result<(), Error>;
+}
@@ -0,0 +1,337 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+
+use super::{Error, ProofID, ProofRequestState, ProofRequstInformation, Storage};
+
+/// Durable, crash-recoverable `Storage` backed by RocksDB. Every request
+/// record, including its state-machine position, is serialized to disk so a
+/// relay restart does not silently drop proofs that were `Pending`,
+/// `Completed`, or `PreparingOnchain` when the process died.
+///
+/// Keys are the Bonsai proof request ID; values are
+/// `(ProofRequstInformation, ProofRequestState)` encoded with `bincode`.
+#[derive(Clone)]
+pub struct RocksDbStorage {
+    db: Arc<rocksdb::DB>,
+}
+
+impl RocksDbStorage {
+    /// Opens (creating if necessary) a RocksDB database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+        let db = rocksdb::DB::open(&options, path)?;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    fn get_record(
+        &self,
+        id: &str,
+    ) -> Result<(ProofRequstInformation, ProofRequestState), Error> {
+        let bytes = self
+            .db
+            .get(id)
+            .map_err(|err| Error::Backend(err.into()))?
+            .ok_or_else(|| Error::ProofNotFound { id: id.to_string() })?;
+        bincode::deserialize(&bytes).map_err(|err| Error::Backend(err.into()))
+    }
+
+    fn put_record(
+        &self,
+        id: &str,
+        record: &(ProofRequstInformation, ProofRequestState),
+    ) -> Result<(), Error> {
+        let bytes = bincode::serialize(record).map_err(|err| Error::Backend(err.into()))?;
+        self.db.put(id, bytes).map_err(|err| Error::Backend(err.into()))
+    }
+
+    /// Every tracked request, regardless of state. Used by [`recover`] to
+    /// re-enqueue non-terminal requests on startup.
+    fn all_records(&self) -> Result<Vec<(ProofRequstInformation, ProofRequestState)>, Error> {
+        self.db
+            .iterator(rocksdb::IteratorMode::Start)
+            .filter(|entry| {
+                !matches!(entry, Ok((key, _)) if key.as_ref() == LAST_PROCESSED_BLOCK_KEY.as_bytes())
+            })
+            .map(|entry| {
+                let (_, value) = entry.map_err(|err| Error::Backend(err.into()))?;
+                bincode::deserialize(&value).map_err(|err| Error::Backend(err.into()))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Storage for RocksDbStorage {
+    async fn add_new_bonsai_proof_request(
+        &self,
+        info: ProofRequstInformation,
+    ) -> Result<(), Error> {
+        if self.db.key_may_exist(&info.proof_request_id) && self.get_record(&info.proof_request_id).is_ok() {
+            return Err(Error::ProofAlreadyExists {
+                id: info.proof_request_id,
+            });
+        }
+        let id = info.proof_request_id.clone();
+        self.put_record(&id, &(info, ProofRequestState::Pending))
+    }
+
+    async fn transition_proof_request(
+        &self,
+        id: ProofID,
+        state: ProofRequestState,
+    ) -> Result<(), Error> {
+        if state == ProofRequestState::CompletedOnchain {
+            return self.remove_proof_request(id).await;
+        }
+        let (info, _) = self.get_record(&id)?;
+        self.put_record(&id, &(info, state))
+    }
+
+    async fn get_proof_request_state(&self, id: ProofID) -> Result<ProofRequestState, Error> {
+        self.get_record(&id).map(|(_, state)| state)
+    }
+
+    async fn get_proof_request_information(
+        &self,
+        id: ProofID,
+    ) -> Result<ProofRequstInformation, Error> {
+        self.get_record(&id).map(|(info, _)| info)
+    }
+
+    async fn fetch_requests_in_state(
+        &self,
+        state: ProofRequestState,
+    ) -> Result<Vec<ProofRequstInformation>, Error> {
+        Ok(self
+            .all_records()?
+            .into_iter()
+            .filter(|(_, s)| *s == state)
+            .map(|(info, _)| info)
+            .collect())
+    }
+
+    async fn remove_proof_request(&self, id: ProofID) -> Result<(), Error> {
+        // Confirm the record exists so callers get `ProofNotFound` instead
+        // of a silent no-op, matching `InMemoryStorage`.
+        self.get_record(&id)?;
+        self.db
+            .delete(&id)
+            .map_err(|err| Error::Backend(err.into()))
+    }
+
+    async fn get_last_processed_block(&self) -> Result<Option<u64>, Error> {
+        let bytes = self
+            .db
+            .get(LAST_PROCESSED_BLOCK_KEY)
+            .map_err(|err| Error::Backend(err.into()))?;
+        bytes
+            .map(|bytes| {
+                bincode::deserialize(&bytes).map_err(|err| Error::Backend(err.into()))
+            })
+            .transpose()
+    }
+
+    async fn set_last_processed_block(&self, block: u64) -> Result<(), Error> {
+        let bytes = bincode::serialize(&block).map_err(|err| Error::Backend(err.into()))?;
+        self.db
+            .put(LAST_PROCESSED_BLOCK_KEY, bytes)
+            .map_err(|err| Error::Backend(err.into()))
+    }
+}
+
+/// Reserved key (not a valid proof request ID) storing the event listener's
+/// checkpoint in the same column family as proof request records.
+const LAST_PROCESSED_BLOCK_KEY: &str = "__last_processed_block__";
+
+/// Re-enqueues every request found in a non-terminal state after a restart:
+/// `Pending` requests are re-notified to `notify_pending` (driving them back
+/// into `BonsaiPendingProofManager`), and `Completed` requests are
+/// re-notified to `notify_complete` (driving them back into
+/// `BonsaiCompleteProofManager`). Called once, on startup, before either
+/// manager begins processing live events.
+///
+/// `BonsaiCompleteProofManager` only persists a request's submitted-batch
+/// bookkeeping (tx hash, nonce, gas price) in memory, not in `Storage`, so a
+/// request that was `PreparingOnchain` when the process died has no
+/// submitted transaction left to resume tracking. Rather than strand it,
+/// `recover` requeues it as `Completed` so it gets rebatched and resubmitted
+/// from scratch; `fetch_completed_requests` is the only thing that consumes
+/// `notify_complete`, and it only looks at `Completed` requests, so those
+/// must actually be in that state before being notified.
+pub async fn recover(
+    storage: &RocksDbStorage,
+    notify_pending: &std::sync::Arc<tokio::sync::Notify>,
+    notify_complete: &std::sync::Arc<tokio::sync::Notify>,
+) -> Result<(), Error> {
+    let pending = storage.fetch_requests_in_state(ProofRequestState::Pending).await?;
+    if !pending.is_empty() {
+        notify_pending.notify_one();
+    }
+
+    let preparing = storage
+        .fetch_requests_in_state(ProofRequestState::PreparingOnchain)
+        .await?;
+    for request in preparing {
+        storage
+            .transition_proof_request(request.proof_request_id, ProofRequestState::Completed)
+            .await?;
+    }
+
+    let completed = storage
+        .fetch_requests_in_state(ProofRequestState::Completed)
+        .await?;
+    if !completed.is_empty() {
+        notify_complete.notify_one();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    use bonsai_proxy_contract::CallbackRequestFilter;
+    use ethers::types::{Address, Bytes, H256};
+    use futures::FutureExt;
+    use tokio::sync::Notify;
+
+    use super::*;
+
+    /// Opens a fresh RocksDB database in a unique temp directory so tests
+    /// don't interfere with each other or with a real relay's database.
+    fn temp_storage() -> RocksDbStorage {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "bonsai-relay-storage-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        RocksDbStorage::open(path).expect("should open rocksdb")
+    }
+
+    fn sample_request(id: &str) -> ProofRequstInformation {
+        ProofRequstInformation {
+            proof_request_id: id.to_string(),
+            callback_proof_request_event: CallbackRequestFilter {
+                account: Address::default(),
+                image_id: H256::default().into(),
+                input: Bytes::default(),
+                callback_contract: Address::default(),
+                function_selector: [0xab, 0xcd, 0xef, 0xab],
+                gas_limit: 3_000_000,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn round_trips_through_every_state_and_evicts_on_completion() {
+        let storage = temp_storage();
+        storage.add_new_bonsai_proof_request(sample_request("a")).await.unwrap();
+        assert_eq!(
+            storage.get_proof_request_state("a".to_string()).await.unwrap(),
+            ProofRequestState::Pending
+        );
+
+        storage
+            .transition_proof_request("a".to_string(), ProofRequestState::Completed)
+            .await
+            .unwrap();
+        storage
+            .transition_proof_request("a".to_string(), ProofRequestState::PreparingOnchain)
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_proof_request_state("a".to_string()).await.unwrap(),
+            ProofRequestState::PreparingOnchain
+        );
+
+        storage
+            .transition_proof_request("a".to_string(), ProofRequestState::CompletedOnchain)
+            .await
+            .unwrap();
+        assert!(matches!(
+            storage.get_proof_request_state("a".to_string()).await,
+            Err(Error::ProofNotFound { id }) if id == "a"
+        ));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_survives_reopening_the_database() {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "bonsai-relay-storage-checkpoint-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        {
+            let storage = RocksDbStorage::open(&path).unwrap();
+            assert_eq!(storage.get_last_processed_block().await.unwrap(), None);
+            storage.set_last_processed_block(42).await.unwrap();
+        }
+
+        let reopened = RocksDbStorage::open(&path).unwrap();
+        assert_eq!(reopened.get_last_processed_block().await.unwrap(), Some(42));
+    }
+
+    #[tokio::test]
+    async fn recover_requeues_preparing_onchain_requests_as_completed() {
+        let storage = temp_storage();
+        storage.add_new_bonsai_proof_request(sample_request("b")).await.unwrap();
+        storage
+            .transition_proof_request("b".to_string(), ProofRequestState::Completed)
+            .await
+            .unwrap();
+        storage
+            .transition_proof_request("b".to_string(), ProofRequestState::PreparingOnchain)
+            .await
+            .unwrap();
+
+        let notify_pending = Arc::new(Notify::new());
+        let notify_complete = Arc::new(Notify::new());
+        recover(&storage, &notify_pending, &notify_complete).await.unwrap();
+
+        assert_eq!(
+            storage.get_proof_request_state("b".to_string()).await.unwrap(),
+            ProofRequestState::Completed,
+            "a request stranded mid-submission should be requeued for rebatching, not left behind"
+        );
+        // `fetch_completed_requests` is the only consumer of `notify_complete`
+        // and only looks at `Completed` requests, so recover must have fired
+        // it after requeuing, not before.
+        assert!(notify_complete.notified().now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn recover_notifies_pending_when_work_is_outstanding() {
+        let storage = temp_storage();
+        storage.add_new_bonsai_proof_request(sample_request("c")).await.unwrap();
+
+        let notify_pending = Arc::new(Notify::new());
+        let notify_complete = Arc::new(Notify::new());
+        recover(&storage, &notify_pending, &notify_complete).await.unwrap();
+
+        assert!(notify_pending.notified().now_or_never().is_some());
+        assert!(notify_complete.notified().now_or_never().is_none());
+    }
+}
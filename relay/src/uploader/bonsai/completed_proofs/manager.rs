@@ -0,0 +1,303 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Result;
+use bonsai_proxy_contract::BonsaiProxyContract;
+use ethers::{
+    middleware::Middleware,
+    types::{Address, TxHash, U256},
+};
+use tokio::{sync::Notify, time::Interval};
+
+use crate::{
+    prover::ProverBackend,
+    storage::{ProofID, ProofRequestState, ProofRequstInformation, Storage},
+};
+
+/// How many confirmations a submitted batch needs before its requests are
+/// considered final and evicted from storage.
+const REQUIRED_CONFIRMATIONS: u64 = 2;
+
+/// How long to wait for a submitted batch to be mined before resubmitting it
+/// with a bumped gas price.
+const RESUBMIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Percentage by which the prior gas price is raised on resubmission.
+const GAS_BUMP_PERCENT: u64 = 20;
+
+/// A request whose Bonsai proof has been fetched and is waiting to be
+/// included in the next on-chain batch.
+struct StagedProof {
+    proof_id: ProofID,
+    request: ProofRequstInformation,
+    journal: Vec<u8>,
+    seal: Vec<u8>,
+}
+
+/// A batch that has been submitted on-chain and is awaiting confirmation.
+struct SubmittedBatch {
+    tx_hash: TxHash,
+    nonce: U256,
+    gas_price: U256,
+    submitted_at: Instant,
+    proofs: Vec<StagedProof>,
+}
+
+/// Batches `Completed` Bonsai proof requests and submits them on-chain
+/// through the proxy contract. `new_complete_proofs_notifier` signals that
+/// new `Completed` requests may be available in storage; `send_batch_notifier`
+/// and `send_batch_interval` independently trigger flushing the current
+/// batch, whichever comes first.
+pub struct BonsaiCompleteProofManager<S: Storage, M: Middleware + 'static, P: ProverBackend> {
+    prover: P,
+    storage: S,
+    new_complete_proofs_notifier: Arc<Notify>,
+    send_batch_notifier: Arc<Notify>,
+    max_batch_size: usize,
+    proxy_address: Address,
+    ethers_client: Arc<M>,
+    send_batch_interval: Interval,
+    staged: Vec<StagedProof>,
+    batch: Vec<StagedProof>,
+    in_flight: Option<SubmittedBatch>,
+}
+
+impl<S: Storage, M: Middleware + 'static, P: ProverBackend> BonsaiCompleteProofManager<S, M, P> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        prover: P,
+        storage: S,
+        new_complete_proofs_notifier: Arc<Notify>,
+        send_batch_notifier: Arc<Notify>,
+        max_batch_size: usize,
+        proxy_address: Address,
+        ethers_client: Arc<M>,
+        send_batch_interval: Interval,
+    ) -> Self {
+        Self {
+            prover,
+            storage,
+            new_complete_proofs_notifier,
+            send_batch_notifier,
+            max_batch_size,
+            proxy_address,
+            ethers_client,
+            send_batch_interval,
+            staged: Vec::new(),
+            batch: Vec::new(),
+            in_flight: None,
+        }
+    }
+
+    /// Advances the manager by one unit of work: moving a freshly-fetched
+    /// proof into the batch, checking on a submitted batch's confirmation
+    /// status, fetching newly `Completed` requests from Bonsai, or
+    /// submitting the batch on-chain.
+    pub async fn step(&mut self) -> Result<()> {
+        // A proof that finished fetching on a prior step is staged but not
+        // yet batched; batch it before waiting on anything else so the two
+        // phases of "fetch" and "batch" are always visible as distinct steps.
+        if let Some(staged) = self.staged.pop() {
+            self.batch.push(staged);
+            if self.batch.len() >= self.max_batch_size {
+                self.send_batch().await?;
+            }
+            return Ok(());
+        }
+
+        if self.in_flight.is_some() {
+            return self.check_confirmation().await;
+        }
+
+        tokio::select! {
+            _ = self.new_complete_proofs_notifier.notified() => self.fetch_completed_requests().await,
+            _ = self.send_batch_notifier.notified() => self.send_batch().await,
+            _ = self.send_batch_interval.tick() => self.send_batch().await,
+        }
+    }
+
+    /// Fetches the journal and seal for every request currently `Completed`
+    /// in storage, stages it for batching, and transitions it to
+    /// `PreparingOnchain`.
+    async fn fetch_completed_requests(&mut self) -> Result<()> {
+        let requests = self
+            .storage
+            .fetch_requests_in_state(ProofRequestState::Completed)
+            .await?;
+        for request in requests {
+            let proof_id = request.proof_request_id.clone();
+            let output = self.prover.fetch_output(&proof_id).await?;
+            self.storage
+                .transition_proof_request(proof_id.clone(), ProofRequestState::PreparingOnchain)
+                .await?;
+            self.staged.push(StagedProof {
+                proof_id,
+                request,
+                journal: output.journal,
+                seal: output.seal,
+            });
+        }
+        Ok(())
+    }
+
+    /// Submits the current batch to the proxy contract as a single
+    /// transaction and starts tracking it for confirmation. Requests stay in
+    /// `PreparingOnchain` until [`check_confirmation`] sees the required
+    /// confirmation depth.
+    async fn send_batch(&mut self) -> Result<()> {
+        if self.batch.is_empty() || self.in_flight.is_some() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.batch);
+
+        // Use the pending-block nonce, not just the last mined one, so
+        // back-to-back batches don't race for the same nonce. Omitting the
+        // block tag resolves to `latest`, not `pending`, so it must be
+        // passed explicitly.
+        let nonce = self
+            .ethers_client
+            .get_transaction_count(
+                self.ethers_client.default_sender().unwrap_or_default(),
+                Some(ethers::types::BlockId::Number(
+                    ethers::types::BlockNumber::Pending,
+                )),
+            )
+            .await?;
+        let gas_price = self.ethers_client.get_gas_price().await?;
+
+        let tx_hash = self.submit(&batch, nonce, gas_price).await?;
+        self.in_flight = Some(SubmittedBatch {
+            tx_hash,
+            nonce,
+            gas_price,
+            submitted_at: Instant::now(),
+            proofs: batch,
+        });
+        Ok(())
+    }
+
+    /// Builds and sends the batch transaction at a fixed `nonce`/`gas_price`,
+    /// returning its hash without waiting for it to be mined.
+    async fn submit(
+        &self,
+        batch: &[StagedProof],
+        nonce: U256,
+        gas_price: U256,
+    ) -> Result<TxHash> {
+        let contract = BonsaiProxyContract::new(self.proxy_address, self.ethers_client.clone());
+        let calls = batch
+            .iter()
+            .map(|proof| {
+                (
+                    proof.request.callback_proof_request_event.callback_contract,
+                    proof.request.callback_proof_request_event.function_selector,
+                    proof.request.callback_proof_request_event.gas_limit,
+                    proof.journal.clone(),
+                    proof.seal.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let pending_tx = contract
+            .invoke_proof_verify_batch(calls)
+            .nonce(nonce)
+            .gas_price(gas_price)
+            .send()
+            .await?;
+        Ok(pending_tx.tx_hash())
+    }
+
+    /// Checks the in-flight batch's confirmation depth: finalizes it once
+    /// `REQUIRED_CONFIRMATIONS` blocks have passed, resubmits with a bumped
+    /// gas price if `RESUBMIT_TIMEOUT` elapses before it's mined. Its
+    /// requests stay `PreparingOnchain` until finalized; a transaction that
+    /// a reorg un-mines has no receipt again and is handled like an unmined
+    /// one, resubmitted once `RESUBMIT_TIMEOUT` since its last submission
+    /// has elapsed.
+    async fn check_confirmation(&mut self) -> Result<()> {
+        let in_flight = self.in_flight.as_ref().expect("checked by caller");
+
+        let receipt = self
+            .ethers_client
+            .get_transaction_receipt(in_flight.tx_hash)
+            .await?;
+
+        let Some(receipt) = receipt else {
+            if in_flight.submitted_at.elapsed() >= RESUBMIT_TIMEOUT {
+                let in_flight = self.in_flight.take().expect("checked above");
+                let bumped_gas_price = bump_gas_price(in_flight.gas_price);
+                let tx_hash = self
+                    .submit(&in_flight.proofs, in_flight.nonce, bumped_gas_price)
+                    .await?;
+                self.in_flight = Some(SubmittedBatch {
+                    tx_hash,
+                    nonce: in_flight.nonce,
+                    gas_price: bumped_gas_price,
+                    submitted_at: Instant::now(),
+                    proofs: in_flight.proofs,
+                });
+            }
+            return Ok(());
+        };
+
+        let head = self.ethers_client.get_block_number().await?.as_u64();
+        let confirmations = confirmation_depth(head, receipt.block_number.map(|block| block.as_u64()));
+
+        if confirmations < REQUIRED_CONFIRMATIONS {
+            return Ok(());
+        }
+
+        let in_flight = self.in_flight.take().expect("checked above");
+        for proof in in_flight.proofs {
+            self.storage
+                .transition_proof_request(proof.proof_id, ProofRequestState::CompletedOnchain)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies [`GAS_BUMP_PERCENT`] to `gas_price` for a resubmission.
+fn bump_gas_price(gas_price: U256) -> U256 {
+    gas_price * (100 + GAS_BUMP_PERCENT) / 100
+}
+
+/// How many blocks deep a transaction mined at `mined_block` is relative to
+/// `head`, counting the mining block itself as the first confirmation.
+/// `None` (no receipt yet) has zero confirmations.
+fn confirmation_depth(head: u64, mined_block: Option<u64>) -> u64 {
+    mined_block
+        .map(|block| head.saturating_sub(block) + 1)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_gas_price_applies_the_configured_percentage() {
+        assert_eq!(bump_gas_price(U256::from(100)), U256::from(120));
+        assert_eq!(bump_gas_price(U256::zero()), U256::zero());
+    }
+
+    #[test]
+    fn confirmation_depth_counts_the_mining_block_itself() {
+        // Mined in the current head block: one confirmation, not zero.
+        assert_eq!(confirmation_depth(10, Some(10)), 1);
+        assert_eq!(confirmation_depth(12, Some(10)), 3);
+        assert_eq!(confirmation_depth(10, None), 0);
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use tokio::sync::{Notify, Semaphore};
+
+use crate::{
+    prover::ProverBackend,
+    storage::{ProofID, ProofRequestState, Storage},
+};
+
+/// Drives proof requests that are `Pending` in `Storage` through to
+/// completion against a [`ProverBackend`]. `notifier` signals that new
+/// `Pending` requests may be available; `done_notifier` is signaled once a
+/// tracked request transitions to `Completed`. The number of requests polled
+/// against the backend at once is capped by `max_concurrent`, in the style of
+/// a Tower concurrency-limit layer, so a burst of callback events can't
+/// overwhelm the backend or local memory.
+pub struct BonsaiPendingProofManager<S: Storage, P: ProverBackend> {
+    prover: P,
+    storage: S,
+    notifier: Arc<Notify>,
+    done_notifier: Arc<Notify>,
+    permits: Arc<Semaphore>,
+    in_flight: FuturesUnordered<BoxFuture<'static, (ProofID, Result<()>)>>,
+    tracked: HashSet<ProofID>,
+}
+
+impl<S: Storage, P: ProverBackend> BonsaiPendingProofManager<S, P> {
+    /// `max_concurrent` bounds how many proof requests this manager polls at
+    /// once; tune it to the backend's rate limits.
+    pub fn new(
+        prover: P,
+        storage: S,
+        notifier: Arc<Notify>,
+        done_notifier: Arc<Notify>,
+        max_concurrent: usize,
+    ) -> Self {
+        Self {
+            prover,
+            storage,
+            notifier,
+            done_notifier,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            in_flight: FuturesUnordered::new(),
+            tracked: HashSet::new(),
+        }
+    }
+
+    /// Advances the manager by one unit of work: either picking up newly
+    /// `Pending` requests that fit under the concurrency limit (on
+    /// `notifier`), or handling the next request that finished polling
+    /// Bonsai, which releases its permit and admits any requests that were
+    /// previously waiting on one.
+    pub async fn step(&mut self) -> Result<()> {
+        tokio::select! {
+            _ = self.notifier.notified() => self.enqueue_pending_requests().await,
+            Some((proof_id, result)) = self.in_flight.next(), if !self.in_flight.is_empty() => {
+                // Always stop tracking the request, whether it completed or
+                // errored, so a failure (e.g. Bonsai status `FAILED`) can't
+                // wedge it out of `enqueue_pending_requests` forever.
+                self.tracked.remove(&proof_id);
+                if let Err(err) = result {
+                    // Leave the request `Pending` in storage so the next
+                    // `enqueue_pending_requests` call retries it, rather than
+                    // tearing down the caller's loop over a single failed
+                    // request.
+                    eprintln!("proof request {proof_id} failed to complete: {err:#}");
+                } else {
+                    self.storage
+                        .transition_proof_request(proof_id, ProofRequestState::Completed)
+                        .await?;
+                    self.done_notifier.notify_one();
+                }
+                // A permit was just released; admit anything that was
+                // waiting on one.
+                self.enqueue_pending_requests().await
+            }
+        }
+    }
+
+    /// Fetches every request currently `Pending` in storage and starts
+    /// polling Bonsai for any not already being tracked, up to as many
+    /// permits as are currently available. Requests that don't get a permit
+    /// stay untracked and are retried on the next call.
+    async fn enqueue_pending_requests(&mut self) -> Result<()> {
+        let requests = self
+            .storage
+            .fetch_requests_in_state(ProofRequestState::Pending)
+            .await?;
+        for request in requests {
+            let proof_id = request.proof_request_id;
+            if self.tracked.contains(&proof_id) {
+                continue;
+            }
+            let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+                // At capacity; leave this request pending and try again once
+                // a permit frees up.
+                continue;
+            };
+            self.tracked.insert(proof_id.clone());
+            let prover = self.prover.clone();
+            self.in_flight.push(Box::pin(async move {
+                let _permit = permit;
+                let result = prover.wait_for_completion(&proof_id).await;
+                (proof_id, result)
+            }));
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use bonsai_proxy_contract::CallbackRequestFilter;
 use bonsai_sdk::client::Client;
 use ethereum_relay::{
+    prover::bonsai::BonsaiProverBackend,
     storage::{
         in_memory::InMemoryStorage, Error as StorageError, ProofRequestState,
         ProofRequstInformation, Storage,
@@ -28,15 +29,17 @@ async fn integration_test_pending_proof_manager() {
     let (proof_id, server) = utils::get_test_bonsai_server().await;
 
     let client = Client::new(server.uri(), "").unwrap();
+    let prover = BonsaiProverBackend::new(client);
     let storage = InMemoryStorage::new();
     let notifier = Arc::new(Notify::new());
     let done_notifer = Arc::new(Notify::new());
 
     let mut manager = BonsaiPendingProofManager::new(
-        client,
+        prover,
         storage.clone(),
         notifier.clone(),
         done_notifer.clone(),
+        4,
     );
 
     // add a pending proof request to storage
@@ -87,6 +90,7 @@ async fn integration_test_completed_proof_manager() {
     let proxy = utils::deploy_proxy_contract(ethers_client.clone()).await;
 
     let client = Client::new(server.uri(), "").unwrap();
+    let prover = BonsaiProverBackend::new(client);
     let storage = InMemoryStorage::new();
     let new_complete_proofs_notifier = Arc::new(Notify::new());
     let send_batch_notifier = Arc::new(Notify::new());
@@ -101,7 +105,7 @@ async fn integration_test_completed_proof_manager() {
     send_batch_interval.tick().await;
 
     let mut manager = BonsaiCompleteProofManager::new(
-        client,
+        prover,
         storage.clone(),
         new_complete_proofs_notifier.clone(),
         send_batch_notifier.clone(),
@@ -158,7 +162,9 @@ async fn integration_test_completed_proof_manager() {
     // now we can signal that the batch should be sent
     send_batch_notifier.notify_one();
 
-    // third step should actually send the batch to the ethereum network
+    // third step should submit the batch to the ethereum network and start
+    // tracking it for confirmation; the request stays PreparingOnchain until
+    // enough confirmations are observed
     manager.step().await.expect("step should succeed");
 
     // check that the event was emitted
@@ -175,7 +181,21 @@ async fn integration_test_completed_proof_manager() {
         "0xf91ad45be22995db29601925ae62b8fb1c0a2bc3ac736e75866291ad5e6108ce".to_string()
     );
 
-    // verify that the state of the request is CompletedOnchain
+    let request_state = storage
+        .get_proof_request_state(proof_id)
+        .await
+        .expect("proof should still be tracked pending confirmation");
+    assert_eq!(request_state, ProofRequestState::PreparingOnchain);
+
+    // mine a couple more blocks so the confirmation depth is reached
+    ethers_client
+        .provider()
+        .request::<_, ()>("anvil_mine", [2])
+        .await
+        .expect("anvil should mine blocks");
+    manager.step().await.expect("step should succeed");
+
+    // verify that the state of the request is now CompletedOnchain
     let request_state_response = storage.get_proof_request_state(proof_id).await;
     // The proof request should no longer be in the in-memory database since it is
     // completed
@@ -37,6 +37,14 @@ enum Command {
 
         /// The input to provide to the guest binary
         input: Option<String>,
+
+        /// Comma-separated ABI types (e.g. `uint256,address,bytes32`)
+        /// describing `input`, which is then parsed as a JSON array of
+        /// values instead of being handed to the `GuestInterface`. Lets
+        /// operators feed tuples, arrays, and dynamic bytes to a guest
+        /// without a bespoke `parse_input` for every argument shape.
+        #[clap(long)]
+        input_type: Option<String>,
     },
     /// Runs the RISC-V ELF binary on Bonsai
     /// and publish the result to Ethererum.
@@ -64,6 +72,107 @@ enum Command {
         /// The input to provide to the guest binary
         #[clap(short, long)]
         input: String,
+
+        /// Comma-separated ABI types (e.g. `uint256,address,bytes32`)
+        /// describing `input`, which is then parsed as a JSON array of
+        /// values instead of being handed to the `GuestInterface`. Lets
+        /// operators feed tuples, arrays, and dynamic bytes to a guest
+        /// without a bespoke `parse_input` for every argument shape.
+        #[clap(long)]
+        input_type: Option<String>,
+    },
+    /// Runs the RISC-V ELF binary on Bonsai and submits the result as a
+    /// callback through a Bonsai Ethereum Relay.
+    Callback {
+        /// Bonsai Relay URL.
+        #[clap(long, env)]
+        relay_url: String,
+
+        /// Bonsai API key.
+        #[clap(long, env)]
+        bonsai_api_key: String,
+
+        /// Application's contract address on Ethereum
+        #[clap(long)]
+        contract: String,
+
+        /// The name of the guest binary
+        #[clap(long)]
+        guest_binary: String,
+
+        /// The input to provide to the guest binary
+        #[clap(short, long)]
+        input: String,
+
+        /// Gas limit for the relay-submitted callback transaction
+        #[clap(long, default_value_t = 3_000_000)]
+        gas_limit: u64,
+    },
+    /// Registers a guest's current image ID in the on-chain `Registry`, at
+    /// version 1.
+    Register {
+        /// Ethereum chain ID
+        #[clap(long)]
+        chain_id: u64,
+
+        /// Ethereum wallet private key.
+        #[clap(long, env)]
+        eth_wallet_private_key: String,
+
+        /// Ethereum Node endpoint.
+        #[clap(long)]
+        rpc_url: String,
+
+        /// Address of the deployed `Registry` contract
+        #[clap(long)]
+        registry: String,
+
+        /// The name of the guest binary
+        #[clap(long)]
+        guest_binary: String,
+    },
+    /// Updates a guest's image ID in the on-chain `Registry` to a new,
+    /// strictly greater version.
+    Update {
+        /// Ethereum chain ID
+        #[clap(long)]
+        chain_id: u64,
+
+        /// Ethereum wallet private key.
+        #[clap(long, env)]
+        eth_wallet_private_key: String,
+
+        /// Ethereum Node endpoint.
+        #[clap(long)]
+        rpc_url: String,
+
+        /// Address of the deployed `Registry` contract
+        #[clap(long)]
+        registry: String,
+
+        /// The name of the guest binary
+        #[clap(long)]
+        guest_binary: String,
+
+        /// The new version number; must be greater than the currently
+        /// registered version.
+        #[clap(long)]
+        version: u64,
+    },
+    /// Compares the on-chain image ID registered for a guest against the
+    /// image ID that was just built locally, failing loudly on drift.
+    Verify {
+        /// Ethereum Node endpoint.
+        #[clap(long)]
+        rpc_url: String,
+
+        /// Address of the deployed `Registry` contract
+        #[clap(long)]
+        registry: String,
+
+        /// The name of the guest binary
+        #[clap(long)]
+        guest_binary: String,
     },
 }
 
@@ -85,6 +194,39 @@ pub trait GuestInterface {
         post_state_digest: FixedBytes<32>,
         seal: Vec<u8>,
     ) -> Result<Vec<u8>>;
+
+    /// Canonical Solidity signature of the callback function invoked by
+    /// `encode_calldata` (e.g. `set(uint256,bytes32,bytes)`), used to derive
+    /// the 4-byte selector for the `Callback` command. Override this to
+    /// enable Bonsai relay callbacks.
+    fn callback_signature(&self) -> Option<&str> {
+        None
+    }
+
+    /// Canonical Solidity signature of an event (e.g. `Set(uint256)`) that
+    /// the `encode_calldata` transaction is expected to emit. `publish`
+    /// matches this against the transaction receipt's logs by topic0 and
+    /// fails loudly if it's absent. Override alongside `decode_event` to
+    /// enable this check.
+    fn expected_event(&self) -> Option<&str> {
+        None
+    }
+
+    /// Formats the non-indexed `data` field of a log matched via
+    /// `expected_event`, for display after `publish`. The default prints it
+    /// as `0x`-prefixed hex without ABI-decoding it; override to decode it.
+    fn decode_event(&self, data: &[u8]) -> Result<String> {
+        Ok(format!("0x{}", hex::encode(data)))
+    }
+}
+
+/// Computes the 4-byte Solidity function selector for `signature` as the
+/// first four bytes of `keccak256(signature)` — the standard ABI selector
+/// rule — so callers don't need to hardcode a literal byte array.
+fn function_selector(signature: &str) -> [u8; 4] {
+    alloy_primitives::keccak256(signature.as_bytes())[..4]
+        .try_into()
+        .expect("keccak256 digest is at least 4 bytes")
 }
 
 /// Execute or return image id.
@@ -93,11 +235,19 @@ pub fn query(
     guest_list: &[GuestListEntry],
     guest_binary: String,
     input: Option<String>,
+    input_type: Option<String>,
     guest_interface: impl GuestInterface,
 ) -> Result<()> {
     let elf = resolve_guest_entry(guest_list, &guest_binary)?;
     let image_id = compute_image_id(&elf)?;
     let output = match input {
+        // Input provided, with an ABI type schema. Dynamically ABI-encode it
+        // as guest stdin instead of going through `GuestInterface::parse_input`.
+        Some(input) if input_type.is_some() => {
+            let bytes = parse_dynamic_input(&input_type.unwrap(), &input)?;
+            let proof = prover::generate_proof_from_bytes(&elf, bytes)?;
+            hex::encode(proof.abi_encode())
+        }
         // Input provided. Return the Ethereum ABI encoded proof.
         Some(input) => {
             let proof = prover::generate_proof(&elf, guest_interface.parse_input(input)?)?;
@@ -122,26 +272,197 @@ pub fn publish(
     guest_list: &[GuestListEntry],
     guest_binary: String,
     input: String,
+    input_type: Option<String>,
     guest_interface: impl GuestInterface,
 ) -> Result<()> {
     let elf = resolve_guest_entry(guest_list, &guest_binary)?;
     let tx_sender = eth::TxSender::new(chain_id, &rpc_url, &eth_wallet_private_key, &contract)?;
 
-    let input = guest_interface.parse_input(input)?;
     let Proof {
         journal,
         post_state_digest,
         seal,
-    } = prover::generate_proof(&elf, input)?;
+    } = match input_type {
+        // ABI type schema provided. Dynamically ABI-encode the input as
+        // guest stdin instead of going through `GuestInterface::parse_input`.
+        Some(input_type) => {
+            prover::generate_proof_from_bytes(&elf, parse_dynamic_input(&input_type, &input)?)?
+        }
+        None => prover::generate_proof(&elf, guest_interface.parse_input(input)?)?,
+    };
     let calldata = guest_interface.encode_calldata(
         risc0_zkvm::serde::from_slice(journal.as_slice())?,
         post_state_digest,
         seal,
     )?;
 
+    let runtime = tokio::runtime::Runtime::new()?;
+    let receipt = runtime
+        .block_on(tx_sender.send(calldata))?
+        .ok_or_else(|| anyhow!("transaction was not included in a block"))?;
+
+    if receipt.status.unwrap_or_default().is_zero() {
+        return Err(anyhow!(
+            "transaction {:?} reverted",
+            receipt.transaction_hash
+        ));
+    }
+
+    if let Some(signature) = guest_interface.expected_event() {
+        let topic0 = alloy_primitives::FixedBytes::<32>::from(function_selector_topic(signature));
+        let log = receipt
+            .logs
+            .iter()
+            .find(|log| log.topics.first().map(|t| t.0) == Some(topic0.0))
+            .ok_or_else(|| anyhow!("expected event `{signature}` was not emitted"))?;
+        println!(
+            "event {signature}: {}",
+            guest_interface.decode_event(&log.data)?
+        );
+    }
+
+    Ok(())
+}
+
+/// Computes the 32-byte `topic0` of an event as `keccak256(signature)` over
+/// its canonical signature string (e.g. `Set(uint256)`) — the same rule ABI
+/// libraries use to match event logs by signature.
+fn function_selector_topic(signature: &str) -> [u8; 32] {
+    alloy_primitives::keccak256(signature.as_bytes()).0
+}
+
+/// Request a proof and submit it as a callback through a Bonsai Ethereum
+/// Relay, deriving the function selector from the `GuestInterface`'s
+/// canonical signature instead of a hardcoded byte array.
+pub fn callback(
+    relay_url: String,
+    bonsai_api_key: String,
+    contract: String,
+    guest_list: &[GuestListEntry],
+    guest_binary: String,
+    input: String,
+    gas_limit: u64,
+    guest_interface: impl GuestInterface,
+) -> Result<()> {
+    let signature = guest_interface.callback_signature().ok_or_else(|| {
+        anyhow!("this application's GuestInterface does not implement callback_signature")
+    })?;
+    let function_selector = function_selector(signature);
+
+    let elf = resolve_guest_entry(guest_list, &guest_binary)?;
+    let image_id = compute_image_id(&elf)?;
+
+    let input = guest_interface.parse_input(input)?;
+    let input_bytes = bytemuck::cast_slice(&risc0_zkvm::serde::to_vec(&input)?).to_vec();
+
+    let relay_client =
+        bonsai_ethereum_relay::sdk::client::Client::from_parts(relay_url, bonsai_api_key)
+            .context("failed to initialize the Bonsai relay client")?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(relay_client.callback_request(
+        bonsai_ethereum_relay::sdk::client::CallbackRequest {
+            callback_contract: contract.parse().context("invalid contract address")?,
+            function_selector,
+            gas_limit,
+            image_id: <[u8; 32]>::from(image_id).into(),
+            input: input_bytes,
+        },
+    ))?;
+
+    Ok(())
+}
+
+// `IRegistry` mirrors `contracts/Registry.sol`: an on-chain mapping from
+// guest name to its current image ID and release/version counter.
+alloy_sol_types::sol! {
+    interface IRegistry {
+        function register(string calldata guest, bytes32 imageId) external;
+        function update(string calldata guest, bytes32 imageId, uint256 version) external;
+        function imageIdOf(string calldata guest) external view returns (bytes32 imageId, uint256 version);
+    }
+}
+
+/// Registers `guest_binary`'s current image ID in the on-chain `Registry`.
+pub fn register(
+    chain_id: u64,
+    eth_wallet_private_key: String,
+    rpc_url: String,
+    registry: String,
+    guest_list: &[GuestListEntry],
+    guest_binary: String,
+) -> Result<()> {
+    let image_id = compute_image_id(&resolve_guest_entry(guest_list, &guest_binary)?)?;
+    let calldata = IRegistry::IRegistryCalls::register(IRegistry::registerCall {
+        guest: registry_key(&guest_binary),
+        imageId: <[u8; 32]>::from(image_id).into(),
+    })
+    .abi_encode();
+
+    let tx_sender = eth::TxSender::new(chain_id, &rpc_url, &eth_wallet_private_key, &registry)?;
     let runtime = tokio::runtime::Runtime::new()?;
     runtime.block_on(tx_sender.send(calldata))?;
+    Ok(())
+}
 
+/// Updates `guest_binary`'s image ID in the on-chain `Registry` to `version`,
+/// which the contract rejects unless it is strictly greater than the
+/// currently registered version.
+pub fn update_registry(
+    chain_id: u64,
+    eth_wallet_private_key: String,
+    rpc_url: String,
+    registry: String,
+    guest_list: &[GuestListEntry],
+    guest_binary: String,
+    version: u64,
+) -> Result<()> {
+    let image_id = compute_image_id(&resolve_guest_entry(guest_list, &guest_binary)?)?;
+    let calldata = IRegistry::IRegistryCalls::update(IRegistry::updateCall {
+        guest: registry_key(&guest_binary),
+        imageId: <[u8; 32]>::from(image_id).into(),
+        version: alloy_primitives::U256::from(version),
+    })
+    .abi_encode();
+
+    let tx_sender = eth::TxSender::new(chain_id, &rpc_url, &eth_wallet_private_key, &registry)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(tx_sender.send(calldata))?;
+    Ok(())
+}
+
+/// Reads the on-chain image ID registered for `guest_binary` and compares it
+/// against the image ID computed from the locally built ELF, failing loudly
+/// on drift so a stale deployment is caught before it rejects proofs.
+pub fn verify(
+    rpc_url: String,
+    registry: String,
+    guest_list: &[GuestListEntry],
+    guest_binary: String,
+) -> Result<()> {
+    let local_image_id = compute_image_id(&resolve_guest_entry(guest_list, &guest_binary)?)?;
+    let calldata = IRegistry::IRegistryCalls::imageIdOf(IRegistry::imageIdOfCall {
+        guest: registry_key(&guest_binary),
+    })
+    .abi_encode();
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let return_data = runtime.block_on(eth::call(&rpc_url, &registry, calldata))?;
+    let IRegistry::imageIdOfReturn {
+        imageId: onchain_image_id,
+        version,
+    } = IRegistry::imageIdOfCall::abi_decode_returns(&return_data, true)?;
+
+    if onchain_image_id.0 != <[u8; 32]>::from(local_image_id) {
+        return Err(anyhow!(
+            "image ID drift for `{guest_binary}` (on-chain version {version}): \
+             registry has 0x{}, locally built image ID is 0x{}",
+            hex::encode(onchain_image_id),
+            hex::encode(local_image_id),
+        ));
+    }
+
+    println!("`{guest_binary}` is up to date at registry version {version}");
     Ok(())
 }
 
@@ -151,7 +472,8 @@ pub fn run(guest_list: &[GuestListEntry], guest_interface: impl GuestInterface)
         Command::Query {
             guest_binary,
             input,
-        } => query(guest_list, guest_binary, input, guest_interface)?,
+            input_type,
+        } => query(guest_list, guest_binary, input, input_type, guest_interface)?,
         Command::Publish {
             chain_id,
             eth_wallet_private_key,
@@ -159,6 +481,7 @@ pub fn run(guest_list: &[GuestListEntry], guest_interface: impl GuestInterface)
             contract,
             guest_binary,
             input,
+            input_type,
         } => publish(
             chain_id,
             eth_wallet_private_key,
@@ -167,13 +490,163 @@ pub fn run(guest_list: &[GuestListEntry], guest_interface: impl GuestInterface)
             guest_list,
             guest_binary,
             input,
+            input_type,
+            guest_interface,
+        )?,
+        Command::Callback {
+            relay_url,
+            bonsai_api_key,
+            contract,
+            guest_binary,
+            input,
+            gas_limit,
+        } => callback(
+            relay_url,
+            bonsai_api_key,
+            contract,
+            guest_list,
+            guest_binary,
+            input,
+            gas_limit,
             guest_interface,
         )?,
+        Command::Register {
+            chain_id,
+            eth_wallet_private_key,
+            rpc_url,
+            registry,
+            guest_binary,
+        } => register(
+            chain_id,
+            eth_wallet_private_key,
+            rpc_url,
+            registry,
+            guest_list,
+            guest_binary,
+        )?,
+        Command::Update {
+            chain_id,
+            eth_wallet_private_key,
+            rpc_url,
+            registry,
+            guest_binary,
+            version,
+        } => update_registry(
+            chain_id,
+            eth_wallet_private_key,
+            rpc_url,
+            registry,
+            guest_list,
+            guest_binary,
+            version,
+        )?,
+        Command::Verify {
+            rpc_url,
+            registry,
+            guest_binary,
+        } => verify(rpc_url, registry, guest_list, guest_binary)?,
     }
 
     Ok(())
 }
 
+/// Parses `input` as a JSON array of values matching the comma-separated ABI
+/// types in `input_type` (e.g. `uint256,address,bytes32`) and ABI-encodes
+/// the resulting tuple, using ethabi's dynamic `Token` decoding. This lets
+/// operators feed tuples, arrays, and dynamic bytes to a guest at runtime
+/// without a bespoke `parse_input` for every argument shape.
+fn parse_dynamic_input(input_type: &str, input: &str) -> Result<Vec<u8>> {
+    let param_types = input_type
+        .split(',')
+        .map(|ty| ethabi::param_type::Reader::read(ty.trim()))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| anyhow!("invalid --input-type: {err}"))?;
+
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(input).context("--input must be a JSON array matching --input-type")?;
+    if values.len() != param_types.len() {
+        return Err(anyhow!(
+            "--input has {} value(s) but --input-type declares {} type(s)",
+            values.len(),
+            param_types.len()
+        ));
+    }
+
+    let tokens = param_types
+        .iter()
+        .zip(values.iter())
+        .map(|(ty, value)| json_to_token(ty, value))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ethabi::encode(&tokens))
+}
+
+/// Recursively converts a JSON value into an `ethabi::Token` of the declared
+/// `param_type`.
+fn json_to_token(param_type: &ethabi::ParamType, value: &serde_json::Value) -> Result<ethabi::Token> {
+    use ethabi::{ParamType, Token};
+    match param_type {
+        ParamType::Uint(_) | ParamType::Int(_) => {
+            // Accept a bare JSON number (e.g. `42`) as well as a decimal
+            // string (e.g. `"42"`); `--input '[42,"0x..","0x..."]'` from this
+            // flag's own usage example is otherwise rejected.
+            let s = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => return Err(anyhow!("expected a decimal string or number for {param_type}")),
+            };
+            Ok(Token::Uint(ethabi::ethereum_types::U256::from_dec_str(&s)?))
+        }
+        ParamType::Address => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| anyhow!("expected a hex string for {param_type}"))?;
+            Ok(Token::Address(s.trim_start_matches("0x").parse()?))
+        }
+        ParamType::Bool => Ok(Token::Bool(
+            value.as_bool().ok_or_else(|| anyhow!("expected a bool"))?,
+        )),
+        ParamType::Bytes | ParamType::FixedBytes(_) => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| anyhow!("expected a hex string for {param_type}"))?;
+            let bytes = hex::decode(s.trim_start_matches("0x"))?;
+            match param_type {
+                ParamType::Bytes => Ok(Token::Bytes(bytes)),
+                _ => Ok(Token::FixedBytes(bytes)),
+            }
+        }
+        ParamType::String => Ok(Token::String(
+            value
+                .as_str()
+                .ok_or_else(|| anyhow!("expected a string"))?
+                .to_string(),
+        )),
+        ParamType::Array(inner) => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| anyhow!("expected a JSON array for {param_type}"))?;
+            Ok(Token::Array(
+                items
+                    .iter()
+                    .map(|item| json_to_token(inner, item))
+                    .collect::<Result<Vec<_>>>()?,
+            ))
+        }
+        other => Err(anyhow!(
+            "unsupported ABI type in dynamic input parsing: {other}"
+        )),
+    }
+}
+
+/// Canonicalizes a guest name into its on-chain `Registry` key, applying the
+/// same normalization `resolve_guest_entry` uses to look up a guest by name,
+/// so the registry key is stable regardless of how the name is cased on the
+/// command line.
+fn registry_key(guest_binary: &str) -> String {
+    guest_binary.to_uppercase()
+}
+
 fn resolve_guest_entry(guest_list: &[GuestListEntry], guest_binary: &String) -> Result<Vec<u8>> {
     // Search list for requested binary name
     let potential_guest_image_id: [u8; 32] =
@@ -201,3 +674,62 @@ fn resolve_guest_entry(guest_list: &[GuestListEntry], guest_binary: &String) ->
         .cloned()?;
     Ok(guest_entry.elf.to_vec())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_selector_matches_the_standard_abi_selector_rule() {
+        // keccak256("set(uint256,bytes32,bytes)")[..4], computed
+        // independently via the same rule ABI libraries use.
+        assert_eq!(
+            function_selector("set(uint256,bytes32,bytes)"),
+            [0x61, 0x4d, 0x40, 0x90]
+        );
+        assert_ne!(
+            function_selector("set(uint256,bytes32,bytes)"),
+            function_selector("get(uint256,bytes32,bytes)")
+        );
+    }
+
+    #[test]
+    fn function_selector_topic_matches_the_keccak256_event_signature_hash() {
+        // keccak256("Set(uint256)"), computed independently via the same
+        // rule used to match event logs by topic0.
+        assert_eq!(
+            function_selector_topic("Set(uint256)").to_vec(),
+            hex::decode("df7a95aebff315db1b7716215d602ab537373cdb769232aae6055c06e798425b")
+                .unwrap()
+        );
+        assert_ne!(
+            function_selector_topic("Set(uint256)"),
+            function_selector_topic("Other(uint256)")
+        );
+    }
+
+    #[test]
+    fn parse_dynamic_input_accepts_the_flag_s_own_usage_example() {
+        // Exactly the `--input-type 'uint256,address,bytes32' --input
+        // '[42,"0x..","0x.."]'` example from this flag's own description,
+        // with an unquoted number for the uint256.
+        let input_type = "uint256,address,bytes32";
+        let input = r#"[42,"0x000000000000000000000000000000000000dEaD","0x0000000000000000000000000000000000000000000000000000000000000001"]"#;
+
+        let encoded = parse_dynamic_input(input_type, input).unwrap();
+        assert_eq!(encoded.len(), 3 * 32, "three fixed-size ABI words");
+    }
+
+    #[test]
+    fn parse_dynamic_input_rejects_a_value_type_count_mismatch() {
+        assert!(parse_dynamic_input("uint256,address", "[42]").is_err());
+    }
+
+    #[test]
+    fn json_to_token_accepts_both_quoted_and_bare_uint_values() {
+        let ty = ethabi::ParamType::Uint(256);
+        let from_number = json_to_token(&ty, &serde_json::json!(42)).unwrap();
+        let from_string = json_to_token(&ty, &serde_json::json!("42")).unwrap();
+        assert_eq!(from_number, from_string);
+    }
+}
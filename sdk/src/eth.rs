@@ -0,0 +1,83 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal Ethereum transaction signing/submission for the CLI: a local
+//! wallet over a plain JSON-RPC HTTP provider, with no dependency on the
+//! relay's async event-driven machinery.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::Middleware,
+    providers::{Http, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, TransactionReceipt, TransactionRequest},
+};
+
+/// Signs calldata with a local wallet and submits it to a fixed contract
+/// address.
+pub struct TxSender {
+    chain_id: u64,
+    contract: Address,
+    client: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
+}
+
+impl TxSender {
+    /// Creates a `TxSender` that signs with `private_key` and submits
+    /// transactions to `contract` over `rpc_url`.
+    pub fn new(chain_id: u64, rpc_url: &str, private_key: &str, contract: &str) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url).context("invalid RPC URL")?;
+        let wallet = private_key
+            .parse::<LocalWallet>()
+            .context("invalid private key")?
+            .with_chain_id(chain_id);
+        let contract: Address = contract.parse().context("invalid contract address")?;
+        Ok(Self {
+            chain_id,
+            contract,
+            client: Arc::new(SignerMiddleware::new(provider, wallet)),
+        })
+    }
+
+    /// Submits `calldata` to the configured contract and waits for it to be
+    /// mined, returning the receipt. `None` if the node drops the
+    /// transaction before it's ever included in a block.
+    pub async fn send(&self, calldata: Vec<u8>) -> Result<Option<TransactionReceipt>> {
+        let tx = TransactionRequest::new()
+            .chain_id(self.chain_id)
+            .to(self.contract)
+            .data(Bytes::from(calldata));
+        let pending_tx = self
+            .client
+            .send_transaction(tx, None)
+            .await
+            .context("failed to submit transaction")?;
+        pending_tx.await.context("failed to wait for transaction to be mined")
+    }
+}
+
+/// Performs a read-only `eth_call` against `contract` at `rpc_url`,
+/// returning the raw ABI-encoded return data. Used by `verify`, which only
+/// reads on-chain state and has no wallet to sign with.
+pub async fn call(rpc_url: &str, contract: &str, calldata: Vec<u8>) -> Result<Bytes> {
+    let provider = Provider::<Http>::try_from(rpc_url).context("invalid RPC URL")?;
+    let contract: Address = contract.parse().context("invalid contract address")?;
+    let tx = TransactionRequest::new().to(contract).data(Bytes::from(calldata));
+    provider
+        .call(&tx.into(), None)
+        .await
+        .context("eth_call failed")
+}
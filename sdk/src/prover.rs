@@ -0,0 +1,70 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Drives a Bonsai proving session to completion and assembles the
+//! resulting receipt into the ABI-encodable [`Proof`] shape the CLI
+//! publishes on-chain.
+
+use std::time::Duration;
+
+use alloy_primitives::FixedBytes;
+use anyhow::{anyhow, Result};
+use bonsai_sdk::client::Client;
+use risc0_zkvm::compute_image_id;
+
+use crate::snark::Proof;
+
+/// How long to wait between polls of a Bonsai session's status.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Runs `elf` on Bonsai with `input` serialized via `risc0_zkvm::serde` (the
+/// encoding `env::read()` expects in the guest).
+pub fn generate_proof<T: serde::Serialize>(elf: &[u8], input: T) -> Result<Proof> {
+    let input_bytes = bytemuck::cast_slice(&risc0_zkvm::serde::to_vec(&input)?).to_vec();
+    generate_proof_from_bytes(elf, input_bytes)
+}
+
+/// Runs `elf` on Bonsai with `input_bytes` written directly to the guest's
+/// stdin, bypassing `risc0_zkvm::serde` re-serialization. Used by
+/// `--input-type`, whose caller has already ABI-encoded the input itself —
+/// re-serializing already-encoded bytes through `risc0_zkvm::serde` would
+/// double-encode them.
+pub fn generate_proof_from_bytes(elf: &[u8], input_bytes: Vec<u8>) -> Result<Proof> {
+    let client = Client::from_env(risc0_zkvm::VERSION)?;
+
+    let image_id_hex = hex::encode(compute_image_id(elf)?);
+    let input_id = client.upload_input(input_bytes)?;
+    let session = client.create_session(image_id_hex, input_id, vec![])?;
+
+    loop {
+        let status = client.get_status(&session.uuid)?;
+        match status.status.as_str() {
+            "RUNNING" => std::thread::sleep(POLL_INTERVAL),
+            "SUCCEEDED" => break,
+            other => {
+                return Err(anyhow!(
+                    "bonsai session {} ended in status {other}",
+                    session.uuid
+                ))
+            }
+        }
+    }
+
+    let receipt = client.get_receipt(&session.uuid)?;
+    Ok(Proof {
+        journal: receipt.journal,
+        post_state_digest: FixedBytes::<32>::from_slice(&receipt.post_state_digest),
+        seal: receipt.seal,
+    })
+}